@@ -3,17 +3,16 @@
 // This line prevents a console window from popping up in release builds
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-// --- THIS IS THE FIX ---
-// We only need the ShellExt trait to get the .shell() method
-// The 'unused import' warning is gone because we removed 'tauri::Manager'
+// The ShellExt trait provides the .shell() method; Emitter provides .emit();
+// CommandEvent lets us match on the sidecar's stdout lines.
+use tauri::Emitter;
+use tauri_plugin_shell::process::CommandEvent;
 use tauri_plugin_shell::ShellExt;
 
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init()) // <-- Initialize the shell plugin
         .setup(|app| {
-            // --- THIS IS THE NEW CODE THAT RUNS YOUR BACKEND ---
-
             // Get a handle to the app's shell
             let shell = app.shell();
 
@@ -21,20 +20,34 @@ fn main() {
             let sidecar_command = shell.sidecar("filegpt_backend")
                 .expect("failed to create `filegpt_backend` command");
 
-            // Spawn the sidecar process
+            // Handle used to push change events to the frontend from the task below.
+            let app_handle = app.handle().clone();
+
+            // Spawn the sidecar and forward its event stream to the UI.
             tauri::async_runtime::spawn(async move {
-                
-                let output = sidecar_command
+                let (mut rx, child) = sidecar_command
                     .spawn()
                     .expect("Failed to spawn sidecar");
 
-                // --- THIS IS THE FIX ---
-                // The 'output' is a tuple (receiver, child)
-                // We get the pid from the child, which is the second item (index 1)
-                println!("[Backend] PID: {}", output.1.pid());
+                println!("[Backend] PID: {}", child.pid());
+
+                // Each stdout line from the sidecar is one JSON change event.
+                while let Some(event) = rx.recv().await {
+                    if let CommandEvent::Stdout(bytes) = event {
+                        let line = String::from_utf8_lossy(&bytes);
+                        let line = line.trim();
+                        if line.is_empty() {
+                            continue;
+                        }
+
+                        // Only forward machine-parseable events; skip diagnostics.
+                        if let Ok(payload) = serde_json::from_str::<serde_json::Value>(line) {
+                            let _ = app_handle.emit("fs-change", payload);
+                        }
+                    }
+                }
             });
-            // --- END OF NEW CODE ---
-            
+
             Ok(())
         })
         .run(tauri::generate_context!())
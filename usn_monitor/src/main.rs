@@ -1,10 +1,17 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::ffi::OsString;
+use std::hash::{Hash, Hasher};
 use std::os::windows::ffi::OsStringExt;
+use std::sync::{Arc, RwLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::mem;
 use std::ffi::c_void;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
 
 use windows::core::*;
 use windows::Win32::Foundation::*;
@@ -13,15 +20,352 @@ use windows::Win32::System::IO::*;
 use windows::Win32::Storage::FileSystem::{
     CreateFileW,
     GetDriveTypeW,
+    GetFinalPathNameByHandleW,
     GetVolumeInformationW,
+    OpenFileById,
+    FILE_ID_DESCRIPTOR,
+    FILE_ID_DESCRIPTOR_0,
+    FILE_NAME_NORMALIZED,
+    FILE_SHARE_DELETE,
     FILE_SHARE_READ,
     FILE_SHARE_WRITE,
+    FileIdType,
     OPEN_EXISTING,
+    VOLUME_NAME_DOS,
     FILE_ATTRIBUTE_NORMAL,
+    FILE_FLAG_BACKUP_SEMANTICS,
     FILE_FLAG_OVERLAPPED,
     FILE_FLAGS_AND_ATTRIBUTES,
 };
 
+/// The NTFS volume root directory always carries file reference number `0x5`;
+/// walking a parent chain stops here.
+const NTFS_ROOT_REFERENCE: u64 = 0x5;
+
+/// Upper bound on the number of ancestors we walk before giving up, guarding
+/// against cycles in a corrupt cache.
+const MAX_PATH_DEPTH: usize = 256;
+
+/// Ceiling on the number of cached reference→name entries. A full-volume MFT
+/// scan plus an unbounded live tail would otherwise grow without limit; once the
+/// cap is hit the oldest entries are evicted (and re-recovered on demand).
+const MAX_RESOLVER_ENTRIES: usize = 1 << 20;
+
+/// Ceiling on the number of cached content hashes, bounded for the same reason.
+const MAX_HASH_ENTRIES: usize = 1 << 20;
+
+/// Default ceiling on file size for content hashing (16 MiB); larger files are
+/// never hashed so a single write can't stall the monitor.
+const DEFAULT_HASH_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+/// How long a reference must be quiet before its coalesced MODIFY is hashed.
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// Fixed seed for the content hash so values are comparable across restarts.
+const HASH_SEED: u64 = 0x0046_696c_6547_5054; // "FileGPT"
+
+/// A single file-system change, serialized as one JSON line per event so the
+/// Tauri frontend can parse it off the sidecar's stdout, index it, and render it.
+#[derive(Serialize)]
+struct ChangeEvent<'a> {
+    operation: &'a str,
+    path: &'a str,
+    #[serde(rename = "type")]
+    file_type: &'a str,
+    extension: &'a str,
+    usn: i64,
+    file_ref: u64,
+    timestamp: String,
+    /// The pre-rename path, present only on `MOVE` events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    old_path: Option<&'a str>,
+}
+
+/// A buffered `USN_REASON_RENAME_OLD_NAME` record awaiting its matching
+/// `USN_REASON_RENAME_NEW_NAME` so the two halves can be paired into one `MOVE`.
+struct PendingRename {
+    path: String,
+    file_type: &'static str,
+    extension: String,
+    usn: i64,
+    timestamp: String,
+}
+
+/// A coalesced MODIFY awaiting its settle window before the file is hashed.
+struct PendingModify {
+    path: String,
+    file_type: &'static str,
+    extension: String,
+    usn: i64,
+    timestamp: String,
+    seen: Instant,
+}
+
+/// Suppresses redundant MODIFY events by hashing file contents and only
+/// emitting when the bytes actually changed.
+///
+/// `USN_REASON_DATA_OVERWRITE`/`DATA_EXTEND` fire on every flush, even when an
+/// app rewrites identical bytes. Each MODIFY is coalesced into `pending`,
+/// resetting a settle timer; once a reference is quiet for `window` its file is
+/// hashed once and compared against `last_hash`, so a burst of writes yields at
+/// most one hash computation and one event.
+struct ModifyDebouncer {
+    max_bytes: u64,
+    window: Duration,
+    pending: HashMap<u64, PendingModify>,
+    last_hash: HashMap<u64, u128>,
+    /// Insertion order of `last_hash` keys, for eviction once it exceeds
+    /// [`MAX_HASH_ENTRIES`].
+    hash_order: VecDeque<u64>,
+}
+
+impl ModifyDebouncer {
+    fn from_env() -> Self {
+        let max_bytes = std::env::var("FILEGPT_HASH_MAX_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_HASH_MAX_BYTES);
+
+        ModifyDebouncer {
+            max_bytes,
+            window: DEBOUNCE_WINDOW,
+            pending: HashMap::new(),
+            last_hash: HashMap::new(),
+            hash_order: VecDeque::new(),
+        }
+    }
+
+    /// Record a MODIFY for `file_ref`, coalescing with any in-flight one and
+    /// resetting its settle timer.
+    fn observe(&mut self, file_ref: u64, pending: PendingModify) {
+        self.pending.insert(file_ref, pending);
+    }
+
+    /// Drop any buffered MODIFY and stored hash for a reference that is going
+    /// away (deleted or renamed). Without this a MODIFY coalesced just before a
+    /// DELETE would flush ~1 s later on a now-missing path and resurrect the
+    /// file in the catalog.
+    fn discard(&mut self, file_ref: u64) {
+        self.pending.remove(&file_ref);
+        self.last_hash.remove(&file_ref);
+    }
+
+    /// Remember the latest content hash for a reference, bounding the map.
+    fn store_hash(&mut self, file_ref: u64, hash: u128) {
+        if self.last_hash.insert(file_ref, hash).is_none() {
+            self.hash_order.push_back(file_ref);
+            while self.last_hash.len() > MAX_HASH_ENTRIES {
+                match self.hash_order.pop_front() {
+                    Some(old) => {
+                        self.last_hash.remove(&old);
+                    }
+                    None => break,
+                }
+            }
+        }
+    }
+
+    /// Emit coalesced MODIFYs whose settle window has elapsed, dropping those
+    /// whose content hash is unchanged since the last emit.
+    fn flush_settled(&mut self, vol_name: &str) {
+        let settled: Vec<u64> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| p.seen.elapsed() >= self.window)
+            .map(|(file_ref, _)| *file_ref)
+            .collect();
+
+        for file_ref in settled {
+            let p = self.pending.remove(&file_ref).unwrap();
+
+            // Too large or unreadable files return `None`: we can't dedup them,
+            // so they fall through and are emitted.
+            if let Some(hash) = hash_file(&p.path, self.max_bytes, HASH_SEED) {
+                if self.last_hash.get(&file_ref) == Some(&hash) {
+                    continue; // identical content: suppress the no-op
+                }
+                self.store_hash(file_ref, hash);
+            }
+
+            emit_event(vol_name, &ChangeEvent {
+                operation: "MODIFY",
+                path: &p.path,
+                file_type: p.file_type,
+                extension: &p.extension,
+                usn: p.usn,
+                file_ref,
+                timestamp: p.timestamp.clone(),
+                old_path: None,
+            });
+        }
+    }
+}
+
+/// Compute a 128-bit MurmurHash3 (x64 variant) of a file's contents, read in
+/// 64-byte blocks. Returns `None` when the file is missing, unreadable, or
+/// larger than `max_bytes` (such files are never hashed to bound the cost).
+fn hash_file(path: &str, max_bytes: u64, seed: u64) -> Option<u128> {
+    use std::io::Read;
+
+    let file = std::fs::File::open(path).ok()?;
+    if file.metadata().ok()?.len() > max_bytes {
+        return None;
+    }
+
+    let mut reader = std::io::BufReader::new(file);
+    let mut state = Murmur3::new(seed);
+    let mut block = [0u8; 64];
+    loop {
+        let n = reader.read(&mut block).ok()?;
+        if n == 0 {
+            break;
+        }
+        state.feed(&block[..n]);
+    }
+    Some(state.finish())
+}
+
+/// Streaming 128-bit MurmurHash3 (x64) state, fed arbitrary-length chunks and
+/// buffering the sub-block remainder between calls.
+struct Murmur3 {
+    h1: u64,
+    h2: u64,
+    tail: [u8; 16],
+    tail_len: usize,
+    total: usize,
+}
+
+impl Murmur3 {
+    const C1: u64 = 0x87c3_7b91_1142_53d5;
+    const C2: u64 = 0x4cf5_ad43_2745_937f;
+
+    fn new(seed: u64) -> Self {
+        Murmur3 {
+            h1: seed,
+            h2: seed,
+            tail: [0u8; 16],
+            tail_len: 0,
+            total: 0,
+        }
+    }
+
+    fn feed(&mut self, mut data: &[u8]) {
+        self.total += data.len();
+
+        // Complete a partially filled block from a previous feed first.
+        if self.tail_len > 0 {
+            while self.tail_len < 16 && !data.is_empty() {
+                self.tail[self.tail_len] = data[0];
+                self.tail_len += 1;
+                data = &data[1..];
+            }
+            if self.tail_len == 16 {
+                let block = self.tail;
+                self.mix_block(&block);
+                self.tail_len = 0;
+            }
+        }
+
+        // Process whole 16-byte blocks.
+        while data.len() >= 16 {
+            let block: [u8; 16] = data[..16].try_into().unwrap();
+            self.mix_block(&block);
+            data = &data[16..];
+        }
+
+        // Stash the remainder for the next feed or the tail in finish().
+        for &b in data {
+            self.tail[self.tail_len] = b;
+            self.tail_len += 1;
+        }
+    }
+
+    fn mix_block(&mut self, block: &[u8; 16]) {
+        let mut k1 = u64::from_le_bytes(block[0..8].try_into().unwrap());
+        let mut k2 = u64::from_le_bytes(block[8..16].try_into().unwrap());
+
+        k1 = k1.wrapping_mul(Self::C1);
+        k1 = k1.rotate_left(31);
+        k1 = k1.wrapping_mul(Self::C2);
+        self.h1 ^= k1;
+        self.h1 = self.h1.rotate_left(27);
+        self.h1 = self.h1.wrapping_add(self.h2);
+        self.h1 = self.h1.wrapping_mul(5).wrapping_add(0x52dce729);
+
+        k2 = k2.wrapping_mul(Self::C2);
+        k2 = k2.rotate_left(33);
+        k2 = k2.wrapping_mul(Self::C1);
+        self.h2 ^= k2;
+        self.h2 = self.h2.rotate_left(31);
+        self.h2 = self.h2.wrapping_add(self.h1);
+        self.h2 = self.h2.wrapping_mul(5).wrapping_add(0x38495ab5);
+    }
+
+    fn finish(mut self) -> u128 {
+        let mut k1: u64 = 0;
+        let mut k2: u64 = 0;
+        let l = self.tail_len;
+
+        if l > 14 { k2 ^= (self.tail[14] as u64) << 48; }
+        if l > 13 { k2 ^= (self.tail[13] as u64) << 40; }
+        if l > 12 { k2 ^= (self.tail[12] as u64) << 32; }
+        if l > 11 { k2 ^= (self.tail[11] as u64) << 24; }
+        if l > 10 { k2 ^= (self.tail[10] as u64) << 16; }
+        if l > 9 { k2 ^= (self.tail[9] as u64) << 8; }
+        if l > 8 {
+            k2 ^= self.tail[8] as u64;
+            k2 = k2.wrapping_mul(Self::C2);
+            k2 = k2.rotate_left(33);
+            k2 = k2.wrapping_mul(Self::C1);
+            self.h2 ^= k2;
+        }
+
+        if l > 7 { k1 ^= (self.tail[7] as u64) << 56; }
+        if l > 6 { k1 ^= (self.tail[6] as u64) << 48; }
+        if l > 5 { k1 ^= (self.tail[5] as u64) << 40; }
+        if l > 4 { k1 ^= (self.tail[4] as u64) << 32; }
+        if l > 3 { k1 ^= (self.tail[3] as u64) << 24; }
+        if l > 2 { k1 ^= (self.tail[2] as u64) << 16; }
+        if l > 1 { k1 ^= (self.tail[1] as u64) << 8; }
+        if l > 0 {
+            k1 ^= self.tail[0] as u64;
+            k1 = k1.wrapping_mul(Self::C1);
+            k1 = k1.rotate_left(31);
+            k1 = k1.wrapping_mul(Self::C2);
+            self.h1 ^= k1;
+        }
+
+        self.h1 ^= self.total as u64;
+        self.h2 ^= self.total as u64;
+        self.h1 = self.h1.wrapping_add(self.h2);
+        self.h2 = self.h2.wrapping_add(self.h1);
+        self.h1 = fmix64(self.h1);
+        self.h2 = fmix64(self.h2);
+        self.h1 = self.h1.wrapping_add(self.h2);
+        self.h2 = self.h2.wrapping_add(self.h1);
+
+        ((self.h2 as u128) << 64) | (self.h1 as u128)
+    }
+}
+
+/// MurmurHash3 64-bit finalization mix.
+fn fmix64(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    k ^= k >> 33;
+    k
+}
+
+/// Write a change as one JSON line on stdout for the frontend to consume.
+fn emit_event(vol_name: &str, event: &ChangeEvent) {
+    match serde_json::to_string(event) {
+        Ok(line) => println!("{}", line),
+        Err(err) => eprintln!("[{}] Failed to serialize event: {}", vol_name, err),
+    }
+}
+
 // Drive type constants
 const DRIVE_FIXED: u32 = 3;
 const DRIVE_REMOVABLE: u32 = 2;
@@ -42,9 +386,162 @@ impl SendPtr {
     }
 }
 
+/// Reconstructs absolute paths for USN records from their file reference numbers.
+///
+/// Each `USN_RECORD_V2` carries only a bare `name` plus its own and its parent's
+/// reference numbers, so a full path has to be stitched together by walking the
+/// parent chain up to the volume root. Names seen on the stream are cached in
+/// `names`; ancestors that were created before the monitor started (and so never
+/// streamed past) are recovered on demand via `OpenFileById` and cached in
+/// `resolved`.
+struct PathResolver {
+    /// `file_ref -> (name, parent_ref)` for every record observed so far.
+    names: HashMap<u64, (String, u64)>,
+    /// `file_ref -> absolute path` for ancestors recovered by `OpenFileById`.
+    resolved: HashMap<u64, String>,
+    /// Insertion order of `names` keys, used to evict the oldest once the cache
+    /// exceeds [`MAX_RESOLVER_ENTRIES`] so a full-volume scan can't leak forever.
+    order: VecDeque<u64>,
+    /// The volume's DOS root, e.g. `C:`.
+    root: String,
+}
+
+impl PathResolver {
+    fn new(root: String) -> Self {
+        PathResolver {
+            names: HashMap::new(),
+            resolved: HashMap::new(),
+            order: VecDeque::new(),
+            root,
+        }
+    }
+
+    /// Record the `(name, parent)` mapping carried by a record as it streams past.
+    ///
+    /// A newer name for a reference supersedes any cold-start path cached in
+    /// `resolved`, so that entry is dropped here — otherwise a renamed directory
+    /// would keep resolving its descendants to the stale path.
+    fn record(&mut self, file_ref: u64, name: &str, parent_ref: u64) {
+        if self.names.insert(file_ref, (name.to_string(), parent_ref)).is_none() {
+            self.order.push_back(file_ref);
+            self.evict_to_bound();
+        }
+        self.resolved.remove(&file_ref);
+    }
+
+    /// Forget a reference once it is deleted, reclaiming both caches.
+    fn forget(&mut self, file_ref: u64) {
+        self.names.remove(&file_ref);
+        self.resolved.remove(&file_ref);
+    }
+
+    /// Evict oldest entries until `names` is back within [`MAX_RESOLVER_ENTRIES`].
+    /// An evicted ancestor is simply re-recovered via `OpenFileById` on demand.
+    fn evict_to_bound(&mut self) {
+        while self.names.len() > MAX_RESOLVER_ENTRIES {
+            match self.order.pop_front() {
+                Some(old) => {
+                    self.names.remove(&old);
+                    self.resolved.remove(&old);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Resolve `file_ref` to an absolute path, walking the parent chain and
+    /// falling back to `OpenFileById` for ancestors not yet seen on the stream.
+    /// Returns `None` when a reference can no longer be opened (e.g. the file
+    /// was deleted), leaving the caller to fall back to the bare name.
+    unsafe fn resolve(&mut self, volume: HANDLE, file_ref: u64) -> Option<String> {
+        let mut components: Vec<String> = Vec::new();
+        let mut reference = file_ref;
+
+        for _ in 0..MAX_PATH_DEPTH {
+            if reference == NTFS_ROOT_REFERENCE || reference == 0 {
+                return Some(self.join(&self.root, &components));
+            }
+            if let Some(base) = self.resolved.get(&reference).cloned() {
+                return Some(self.join(&base, &components));
+            }
+            match self.names.get(&reference).cloned() {
+                Some((name, parent)) => {
+                    components.push(name);
+                    reference = parent;
+                }
+                None => {
+                    // Cold start: this ancestor predates the monitor. Recover its
+                    // full path directly and backfill so later walks terminate here.
+                    let base = path_from_reference(volume, reference)?;
+                    self.resolved.insert(reference, base.clone());
+                    return Some(self.join(&base, &components));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Append the child-first `components` to `base` in root-to-leaf order.
+    fn join(&self, base: &str, components: &[String]) -> String {
+        let mut path = base.trim_end_matches('\\').to_string();
+        for name in components.iter().rev() {
+            path.push('\\');
+            path.push_str(name);
+        }
+        path
+    }
+}
+
+/// Recover the absolute path of a file reference by opening it directly.
+///
+/// The volume handle doubles as the "volume hint" for `OpenFileById`, which
+/// accepts the raw 64-bit reference through a `FILE_ID_DESCRIPTOR`. Backup
+/// semantics are required so directories can be opened too.
+unsafe fn path_from_reference(volume: HANDLE, file_ref: u64) -> Option<String> {
+    let descriptor = FILE_ID_DESCRIPTOR {
+        dwSize: mem::size_of::<FILE_ID_DESCRIPTOR>() as u32,
+        Type: FileIdType,
+        Anonymous: FILE_ID_DESCRIPTOR_0 {
+            FileId: file_ref as i64,
+        },
+    };
+
+    let handle = OpenFileById(
+        volume,
+        &descriptor,
+        0, // no access needed, just a handle for path lookup
+        FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+        None,
+        FILE_FLAG_BACKUP_SEMANTICS,
+    )
+    .ok()?;
+
+    let mut buffer = [0u16; 1024];
+    let len = GetFinalPathNameByHandleW(handle, &mut buffer, FILE_NAME_NORMALIZED | VOLUME_NAME_DOS);
+    let _ = CloseHandle(handle);
+
+    if len == 0 || len as usize > buffer.len() {
+        return None;
+    }
+
+    let path = OsString::from_wide(&buffer[..len as usize])
+        .to_string_lossy()
+        .to_string();
+
+    // GetFinalPathNameByHandleW prefixes DOS paths with the `\\?\` namespace.
+    Some(path.trim_start_matches(r"\\?\").to_string())
+}
+
 fn main() {
-    println!("=== NTFS USN Journal Realtime Monitor (Rust) ===");
-    println!("Monitoring for file system changes (filtering system/temp files)...\n");
+    eprintln!("=== NTFS USN Journal Realtime Monitor (Rust) ===");
+    eprintln!("Monitoring for file system changes (filtering system/temp files)...\n");
+
+    // Load the filter ruleset once and watch the config file for live reloads;
+    // the compiled matcher is shared across every per-volume tail thread.
+    let config_path = filter_config_path();
+    let rules = Arc::new(RwLock::new(load_rules(&config_path)));
+    spawn_rule_watcher(config_path, Arc::clone(&rules));
 
     unsafe {
         let drives_mask = GetLogicalDrives();
@@ -87,7 +584,7 @@ fn main() {
             }
 
             let device_path = format!(r"\\.\{}:", (b'A' + i as u8) as char);
-            println!("✓ Opening NTFS volume {}", device_path);
+            eprintln!("✓ Opening NTFS volume {}", device_path);
 
             // Create null-terminated wide string for CreateFileW
             let mut device_path_wide: Vec<u16> = device_path.encode_utf16().chain(std::iter::once(0)).collect();
@@ -106,10 +603,11 @@ fn main() {
             if let Ok(h) = handle {
                 let h_raw = h.0;
                 let h_send = SendPtr(h_raw);
+                let rules = Arc::clone(&rules);
                 thread::spawn(move || {
                     let h_reconstructed = h_send.into_handle();
                     unsafe {
-                        tail_volume(h_reconstructed, device_path);
+                        tail_volume(h_reconstructed, device_path, rules);
                     }
                 });
             } else {
@@ -172,57 +670,210 @@ fn get_file_type(filename: &str) -> &'static str {
     }
 }
 
-fn is_system_or_temp_file(filename: &str) -> bool {
-    let name_lower = filename.to_lowercase();
-    
-    // Temporary files
-    // if name_lower.starts_with('~') || 
-    //    name_lower.starts_with(".tmp") ||
-    //    name_lower.ends_with(".tmp") ||
-    //    name_lower.ends_with(".temp") ||
-    //    name_lower.contains("~$") ||
-    //    name_lower.contains(".ldb") ||
-    //    name_lower.contains(".log") ||
-    //    name_lower.contains(".vscdb-journal") ||
-    //    name_lower.contains(".interim") ||
-    //    name_lower.contains(".crdownload") ||
-    //    name_lower.contains(".part") ||
-    //    name_lower.contains(".download") {
-    //     return true;
-    // }
-
-    // if name_lower.ends_with(".lnk") ||
-    //    name_lower.ends_with(".url") ||
-    //    name_lower.ends_with(".pf") ||
-    //    name_lower.contains("log_") 
-    //    {
-    //     return true;
-    // }
-    
-    // // Windows system/cache files
-    // if name_lower == "thumbs.db" ||
-    //    name_lower == "desktop.ini" ||
-    //    name_lower == "~wrl0001.tmp" ||
-    //    name_lower.ends_with(".lock") ||
-    //    name_lower.ends_with(".lck") ||
-    //    name_lower.ends_with(".cache") ||
-    //    name_lower.ends_with(".etl") ||
-    //    name_lower.ends_with(".regtrans-ms") ||
-    //    name_lower.ends_with(".blf") ||
-    //    name_lower.ends_with(".$$$") ||
-    //    name_lower.starts_with("$recycle.bin") ||
-    //    name_lower.starts_with("system volume information") ||
-    //    name_lower == "pagefile.sys" ||
-    //    name_lower == "hiberfil.sys" ||
-    //    name_lower == "swapfile.sys" {
-    //     return true;
-    // }
-    
-    false
+/// The built-in ruleset, applied when no config file is present. It can be
+/// fully overridden by supplying a config file (see [`filter_config_path`]).
+///
+/// Each non-comment line is `<include|exclude> <ext|name|path|type> <pattern>`,
+/// where the pattern is a case-insensitive glob (`*`, `?`). Later rules win, so
+/// a specific `include` after a broad `exclude` rescues individual paths.
+const DEFAULT_FILTER_CONFIG: &str = "\
+# FileGPT default filter rules.
+exclude name ~$*
+exclude name *.tmp
+exclude name *.temp
+exclude name *.ldb
+exclude name *.crdownload
+exclude name *.part
+exclude name *.download
+exclude name *.lnk
+exclude name *.url
+exclude name *.pf
+exclude name *.lock
+exclude name *.lck
+exclude name *.cache
+exclude name *.etl
+exclude name *.regtrans-ms
+exclude name *.blf
+exclude name thumbs.db
+exclude name desktop.ini
+exclude name pagefile.sys
+exclude name hiberfil.sys
+exclude name swapfile.sys
+exclude path *\\$recycle.bin\\*
+exclude path *\\system volume information\\*
+";
+
+/// Which field of a record a rule matches against.
+enum RuleField {
+    Extension,
+    Name,
+    Path,
+    Type,
+}
+
+/// Whether a matching rule keeps a path in the index or drops it.
+enum RuleAction {
+    Include,
+    Exclude,
+}
+
+/// One compiled filter rule; `pattern` is stored lowercased for case-insensitive
+/// matching.
+struct Rule {
+    action: RuleAction,
+    field: RuleField,
+    pattern: String,
+}
+
+/// The compiled set of include/exclude rules deciding what FileGPT indexes.
+///
+/// Rules are evaluated in order and the last match wins, mirroring the familiar
+/// gitignore semantics. A path with no matching rule is indexed by default.
+struct FilterRules {
+    rules: Vec<Rule>,
+}
+
+impl FilterRules {
+    /// The built-in default ruleset.
+    fn defaults() -> Self {
+        FilterRules::parse(DEFAULT_FILTER_CONFIG)
+    }
+
+    /// Parse a config file body into a compiled ruleset.
+    fn parse(text: &str) -> Self {
+        let mut rules = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(3, char::is_whitespace);
+            let action = match parts.next() {
+                Some("include") => RuleAction::Include,
+                Some("exclude") => RuleAction::Exclude,
+                _ => continue,
+            };
+            let field = match parts.next() {
+                Some("ext") => RuleField::Extension,
+                Some("name") => RuleField::Name,
+                Some("path") => RuleField::Path,
+                Some("type") => RuleField::Type,
+                _ => continue,
+            };
+            let pattern = match parts.next() {
+                Some(p) => p.trim().to_lowercase(),
+                None => continue,
+            };
+
+            rules.push(Rule { action, field, pattern });
+        }
+
+        FilterRules { rules }
+    }
+
+    /// Decide whether a resolved path should be indexed.
+    fn should_index(&self, path: &str, name: &str, extension: &str, file_type: &str) -> bool {
+        let path_l = path.to_lowercase();
+        let name_l = name.to_lowercase();
+        let ext_l = extension.trim_start_matches('.').to_lowercase();
+        let type_l = file_type.to_lowercase();
+
+        let mut indexed = true;
+        for rule in &self.rules {
+            let value = match rule.field {
+                RuleField::Extension => &ext_l,
+                RuleField::Name => &name_l,
+                RuleField::Path => &path_l,
+                RuleField::Type => &type_l,
+            };
+            if glob_match(&rule.pattern, value) {
+                indexed = matches!(rule.action, RuleAction::Include);
+            }
+        }
+        indexed
+    }
 }
 
-unsafe fn tail_volume(h: HANDLE, vol_name: String) {
-    println!("[{}] Querying USN journal...", vol_name);
+/// Case-insensitive glob match supporting `*` (any run) and `?` (single char).
+///
+/// Both sides are expected to already be lowercased. `*` is greedy with
+/// backtracking and does not treat path separators specially.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pat: Vec<char> = pattern.chars().collect();
+    let txt: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0usize, 0usize);
+    let (mut star, mut mark) = (None, 0usize);
+
+    while t < txt.len() {
+        if p < pat.len() && (pat[p] == '?' || pat[p] == txt[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pat.len() && pat[p] == '*' {
+            star = Some(p);
+            mark = t;
+            p += 1;
+        } else if let Some(sp) = star {
+            p = sp + 1;
+            mark += 1;
+            t = mark;
+        } else {
+            return false;
+        }
+    }
+
+    while p < pat.len() && pat[p] == '*' {
+        p += 1;
+    }
+    p == pat.len()
+}
+
+/// Location of the filter config file, overridable via `FILEGPT_FILTER_CONFIG`.
+fn filter_config_path() -> PathBuf {
+    if let Ok(path) = std::env::var("FILEGPT_FILTER_CONFIG") {
+        return PathBuf::from(path);
+    }
+    let mut path = std::env::temp_dir();
+    path.push("filegpt_filters.conf");
+    path
+}
+
+/// Load rules from the config file, falling back to the built-in defaults when
+/// the file is absent or unreadable.
+fn load_rules(path: &Path) -> FilterRules {
+    match std::fs::read_to_string(path) {
+        Ok(text) => FilterRules::parse(&text),
+        Err(_) => FilterRules::defaults(),
+    }
+}
+
+/// Watch the config file and hot-swap the shared ruleset when it changes.
+fn spawn_rule_watcher(path: PathBuf, rules: Arc<RwLock<FilterRules>>) {
+    thread::spawn(move || {
+        let mut last = file_mtime(&path);
+        loop {
+            thread::sleep(Duration::from_secs(2));
+            let mtime = file_mtime(&path);
+            if mtime != last {
+                last = mtime;
+                let reloaded = load_rules(&path);
+                if let Ok(mut guard) = rules.write() {
+                    *guard = reloaded;
+                }
+                eprintln!("[filter] Reloaded rules from {}", path.display());
+            }
+        }
+    });
+}
+
+/// Last-modified time of a file, or `None` when it does not exist.
+fn file_mtime(path: &Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+unsafe fn tail_volume(h: HANDLE, vol_name: String, rules: Arc<RwLock<FilterRules>>) {
+    eprintln!("[{}] Querying USN journal...", vol_name);
 
     let mut data: USN_JOURNAL_DATA_V0 = mem::zeroed();
     let mut bytes_returned: u32 = 0;
@@ -243,19 +894,43 @@ unsafe fn tail_volume(h: HANDLE, vol_name: String) {
         return;
     }
 
-    println!("[{}] JournalID={} NextUSN={}", vol_name, data.UsnJournalID, data.NextUsn);
-    println!("[{}] ⏳ Waiting for real-time changes...\n", vol_name);
+    eprintln!("[{}] JournalID={} NextUSN={}", vol_name, data.UsnJournalID, data.NextUsn);
+    eprintln!("[{}] ⏳ Waiting for real-time changes...\n", vol_name);
+
+    // Reconstruct absolute paths from the reference numbers on each record.
+    let drive_root = vol_name.trim_start_matches(r"\\.\").to_string();
+    let mut resolver = PathResolver::new(drive_root.clone());
+
+    // Resume from a durable checkpoint when the journal is unchanged; otherwise
+    // start fresh from NextUsn and rebuild the catalog from the MFT. The NextUsn
+    // captured by the query above is the exact cut-over point for a fresh start.
+    let checkpoint_path = checkpoint_path_for(&drive_root);
+    let start_usn = match load_checkpoint(&checkpoint_path) {
+        Some(ckpt) if ckpt.journal_id == data.UsnJournalID => {
+            eprintln!("[{}] Resuming from checkpoint at USN {}", vol_name, ckpt.next_usn);
+            ckpt.next_usn
+        }
+        _ => {
+            // No checkpoint, or the journal was recreated (IDs differ): discard
+            // it and rebuild the catalog from scratch.
+            enumerate_volume(h, &vol_name, &mut resolver, data.NextUsn, &rules);
+            data.NextUsn
+        }
+    };
 
-    // KEY FIX: Use blocking mode with BytesToWaitFor
+    // Wake at least once a second even on an idle volume so coalesced MODIFYs
+    // settle and emit without waiting on unrelated filesystem activity.
     let mut read_data = READ_USN_JOURNAL_DATA_V0 {
-        StartUsn: data.NextUsn,
+        StartUsn: start_usn,
         ReasonMask: 0xFFFFFFFF,
         ReturnOnlyOnClose: 0,
-        Timeout: 0,                    // Infinite timeout - wait forever
+        Timeout: 1,                    // Seconds to block before returning empty
         BytesToWaitFor: 1,             // Wait for at least 1 byte of new data
         UsnJournalID: data.UsnJournalID,
     };
 
+    let volume_hash = volume_hash(&drive_root);
+    let mut debouncer = ModifyDebouncer::from_env();
     let mut buffer = vec![0u8; 64 * 1024]; // 64KB buffer
 
     loop {
@@ -311,15 +986,22 @@ unsafe fn tail_volume(h: HANDLE, vol_name: String) {
             continue;
         }
 
-        // Check if we got any data
+        // A timeout tick returns only the next-USN header and no records; use it
+        // to flush coalesced MODIFYs that have settled on an otherwise-idle
+        // volume, then keep waiting.
         if bytes <= mem::size_of::<i64>() as u32 {
+            debouncer.flush_settled(&vol_name);
             continue;
         }
 
         // Parse USN records from buffer
         // First 8 bytes is the next USN to read from
         let next_usn = *(buffer.as_ptr() as *const i64);
-        
+
+        // Old-name halves of renames wait here for their matching new name,
+        // which almost always arrives later in the same read buffer.
+        let mut pending_renames: HashMap<u64, PendingRename> = HashMap::new();
+
         let mut offset = mem::size_of::<i64>();
         while offset + mem::size_of::<USN_RECORD_V2>() <= bytes as usize {
             let rec = &*(buffer[offset..].as_ptr() as *const USN_RECORD_V2);
@@ -340,61 +1022,388 @@ unsafe fn tail_volume(h: HANDLE, vol_name: String) {
             let name_slice = std::slice::from_raw_parts(name_ptr, name_len_u16);
             let name = OsString::from_wide(name_slice).to_string_lossy().to_string();
 
-            // Skip system and temporary files
-            if is_system_or_temp_file(&name) {
+            // Cache every reference (even filtered ones) so path chains resolve,
+            // then rebuild the absolute path, falling back to the bare name.
+            resolver.record(rec.FileReferenceNumber, &name, rec.ParentFileReferenceNumber);
+            let path = resolver
+                .resolve(h, rec.FileReferenceNumber)
+                .unwrap_or_else(|| name.clone());
+
+            let timestamp = format_timestamp(rec.TimeStamp);
+            let file_ext = get_file_extension(&name);
+            let file_type = get_file_type(&name);
+
+            // Apply the configurable include/exclude rules to the resolved path.
+            let indexed = rules
+                .read()
+                .map(|r| r.should_index(&path, &name, &file_ext, file_type))
+                .unwrap_or(true);
+            if !indexed {
                 offset += rec.RecordLength as usize;
                 continue;
             }
 
-            let timestamp = format_timestamp();
-            let file_ext = get_file_extension(&name);
-            let file_type = get_file_type(&name);
+            // Buffer the old-name half of a rename; its new name pairs it below.
+            if rec.Reason & USN_REASON_RENAME_OLD_NAME != 0 {
+                // A coalesced MODIFY on the old path is now stale; drop it so it
+                // doesn't flush against a path that no longer exists.
+                debouncer.discard(rec.FileReferenceNumber);
+                pending_renames.insert(rec.FileReferenceNumber, PendingRename {
+                    path,
+                    file_type,
+                    extension: file_ext,
+                    usn: rec.Usn,
+                    timestamp,
+                });
+                offset += rec.RecordLength as usize;
+                continue;
+            }
 
-            // Color-coded output based on operation type
-            let operation = if rec.Reason & USN_REASON_FILE_CREATE != 0 {
-                "CREATE"
+            // Pair a new-name record with its buffered old name into one MOVE.
+            let pending = if rec.Reason & USN_REASON_RENAME_NEW_NAME != 0 {
+                pending_renames.remove(&rec.FileReferenceNumber)
+            } else {
+                None
+            };
+
+            let (operation, old_path) = if let Some(old) = &pending {
+                ("MOVE", Some(old.path.as_str()))
+            } else if rec.Reason & USN_REASON_FILE_CREATE != 0 {
+                ("CREATE", None)
             } else if rec.Reason & USN_REASON_FILE_DELETE != 0 {
-                "DELETE"
+                ("DELETE", None)
             } else if rec.Reason & USN_REASON_RENAME_NEW_NAME != 0 {
-                "RENAME"
+                ("RENAME", None)
             } else if rec.Reason & (USN_REASON_DATA_OVERWRITE | USN_REASON_DATA_EXTEND) != 0 {
-                "MODIFY"
+                ("MODIFY", None)
             } else {
-                "CHANGE"
+                ("CHANGE", None)
             };
 
-            println!("[{}] {} | File: {} | Type: {} | Ext: {} | USN: {} | FileRef: {:016X}", 
-                timestamp, 
-                operation, 
-                name, 
-                file_type,
-                file_ext,
-                rec.Usn, 
-                rec.FileReferenceNumber
-            );
+            // Route MODIFY through the debouncer so identical rewrites are
+            // coalesced and hashed once; everything else emits immediately.
+            if operation == "MODIFY" {
+                debouncer.observe(rec.FileReferenceNumber, PendingModify {
+                    path,
+                    file_type,
+                    extension: file_ext,
+                    usn: rec.Usn,
+                    timestamp,
+                    seen: Instant::now(),
+                });
+            } else {
+                // A delete supersedes any MODIFY still coalescing for this ref;
+                // drop it before emitting the DELETE so the stream stays in USN
+                // order and no phantom MODIFY resurrects the file.
+                if operation == "DELETE" {
+                    debouncer.discard(rec.FileReferenceNumber);
+                }
+
+                emit_event(&vol_name, &ChangeEvent {
+                    operation,
+                    path: &path,
+                    file_type,
+                    extension: &file_ext,
+                    usn: rec.Usn,
+                    file_ref: rec.FileReferenceNumber,
+                    timestamp,
+                    old_path,
+                });
+
+                // The reference is gone; reclaim its resolver cache entries.
+                if operation == "DELETE" {
+                    resolver.forget(rec.FileReferenceNumber);
+                }
+            }
 
             offset += rec.RecordLength as usize;
         }
 
-        // Update starting position for next read
+        // Flush old-name records whose new name never arrived in this buffer.
+        for (file_ref, pending) in pending_renames.drain() {
+            emit_event(&vol_name, &ChangeEvent {
+                operation: "RENAME",
+                path: &pending.path,
+                file_type: pending.file_type,
+                extension: &pending.extension,
+                usn: pending.usn,
+                file_ref,
+                timestamp: pending.timestamp.clone(),
+                old_path: None,
+            });
+        }
+
+        // Emit any coalesced MODIFYs whose writes have now settled.
+        debouncer.flush_settled(&vol_name);
+
+        // Update starting position and persist it durably, so a restart resumes
+        // here instead of re-querying NextUsn and losing everything in between.
         read_data.StartUsn = next_usn;
+        let checkpoint = Checkpoint {
+            volume_hash,
+            journal_id: read_data.UsnJournalID,
+            next_usn,
+        };
+        if let Err(err) = save_checkpoint(&checkpoint_path, &checkpoint) {
+            eprintln!("[{}] Failed to write checkpoint: {}", vol_name, err);
+        }
+    }
+}
+
+/// A durable, per-volume record of how far the USN journal has been consumed.
+///
+/// Persisted as a fixed 24-byte little-endian record so a restart can resume
+/// exactly where it left off rather than re-querying `NextUsn` and losing every
+/// change that happened while the monitor was down.
+struct Checkpoint {
+    /// Hash of the drive root (e.g. `C:`) that owns this checkpoint.
+    volume_hash: u64,
+    /// The `UsnJournalID` the checkpoint was taken against; a mismatch means the
+    /// journal was recreated and the checkpoint must be discarded.
+    journal_id: u64,
+    /// Last successfully processed `NextUsn`.
+    next_usn: i64,
+}
+
+/// Size of a serialized [`Checkpoint`] on disk.
+const CHECKPOINT_SIZE: usize = 24;
+
+impl Checkpoint {
+    fn to_bytes(&self) -> [u8; CHECKPOINT_SIZE] {
+        let mut bytes = [0u8; CHECKPOINT_SIZE];
+        bytes[0..8].copy_from_slice(&self.volume_hash.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.journal_id.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.next_usn.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; CHECKPOINT_SIZE]) -> Self {
+        Checkpoint {
+            volume_hash: u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+            journal_id: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            next_usn: i64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+        }
+    }
+}
+
+/// Hash a volume's drive root into the 8-byte key stored in its checkpoint.
+fn volume_hash(drive_root: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    drive_root.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Location of the checkpoint file for a given volume, under the temp directory.
+fn checkpoint_path_for(drive_root: &str) -> PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!("filegpt_usn_{:016x}.ckpt", volume_hash(drive_root)));
+    path
+}
+
+/// Load a checkpoint, returning `None` when it is absent or malformed.
+fn load_checkpoint(path: &Path) -> Option<Checkpoint> {
+    let bytes = std::fs::read(path).ok()?;
+    let fixed: [u8; CHECKPOINT_SIZE] = bytes.as_slice().try_into().ok()?;
+    Some(Checkpoint::from_bytes(&fixed))
+}
+
+/// Persist a checkpoint atomically by writing a temp file and renaming it over
+/// the target, so a crash mid-write can never leave a torn record.
+fn save_checkpoint(path: &Path, checkpoint: &Checkpoint) -> std::io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, checkpoint.to_bytes())?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Walk every existing record in the MFT once to seed the catalog and path cache.
+///
+/// `FSCTL_ENUM_USN_DATA` streams a `USN_RECORD_V2` for each file currently on the
+/// volume. Each is fed through the same classification (`get_file_type`,
+/// `get_file_extension`) and path-resolution the live tail uses, so startup emits
+/// a complete catalog that the tail then extends. `high_usn` is the `NextUsn`
+/// captured by the initial query; records are enumerated only up to it so the
+/// catalog joins the live tail seamlessly.
+unsafe fn enumerate_volume(
+    h: HANDLE,
+    vol_name: &str,
+    resolver: &mut PathResolver,
+    high_usn: i64,
+    rules: &Arc<RwLock<FilterRules>>,
+) {
+    let mut enum_data = MFT_ENUM_DATA_V0 {
+        StartFileReferenceNumber: 0,
+        LowUsn: 0,
+        HighUsn: high_usn,
+    };
+
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut count: u64 = 0;
+
+    loop {
+        let mut bytes: u32 = 0;
+
+        let r = DeviceIoControl(
+            h,
+            FSCTL_ENUM_USN_DATA,
+            Some(&enum_data as *const _ as *const c_void),
+            mem::size_of::<MFT_ENUM_DATA_V0>() as u32,
+            Some(buffer.as_mut_ptr() as *mut c_void),
+            buffer.len() as u32,
+            Some(&mut bytes as *mut u32),
+            None,
+        );
+
+        if let Err(err) = r {
+            // ERROR_HANDLE_EOF marks the end of the enumeration.
+            if err.code().0 == 38 {
+                break;
+            }
+            eprintln!("[{}] FSCTL_ENUM_USN_DATA failed: {:?}", vol_name, err);
+            break;
+        }
+
+        if bytes <= mem::size_of::<i64>() as u32 {
+            break;
+        }
+
+        // The first 8 bytes hold the reference number to resume from next call.
+        let next_ref = *(buffer.as_ptr() as *const u64);
+
+        let mut offset = mem::size_of::<i64>();
+        while offset + mem::size_of::<USN_RECORD_V2>() <= bytes as usize {
+            let rec = &*(buffer[offset..].as_ptr() as *const USN_RECORD_V2);
+
+            if rec.RecordLength == 0 || offset + rec.RecordLength as usize > bytes as usize {
+                break;
+            }
+
+            let name_offset = offset + rec.FileNameOffset as usize;
+            let name_len_u16 = (rec.FileNameLength / 2) as usize;
+
+            if name_offset + (name_len_u16 * 2) > bytes as usize {
+                break;
+            }
+
+            let name_ptr = buffer[name_offset..].as_ptr() as *const u16;
+            let name_slice = std::slice::from_raw_parts(name_ptr, name_len_u16);
+            let name = OsString::from_wide(name_slice).to_string_lossy().to_string();
+
+            // Cache every reference, including directories, so path chains resolve.
+            resolver.record(rec.FileReferenceNumber, &name, rec.ParentFileReferenceNumber);
+
+            let path = resolver
+                .resolve(h, rec.FileReferenceNumber)
+                .unwrap_or_else(|| name.clone());
+            let file_type = get_file_type(&name);
+            let file_ext = get_file_extension(&name);
+
+            let indexed = rules
+                .read()
+                .map(|r| r.should_index(&path, &name, &file_ext, file_type))
+                .unwrap_or(true);
+
+            if indexed {
+                let timestamp = format_timestamp(rec.TimeStamp);
+
+                emit_event(vol_name, &ChangeEvent {
+                    operation: "CATALOG",
+                    path: &path,
+                    file_type,
+                    extension: &file_ext,
+                    usn: rec.Usn,
+                    file_ref: rec.FileReferenceNumber,
+                    timestamp,
+                    old_path: None,
+                });
+
+                count += 1;
+            }
+
+            offset += rec.RecordLength as usize;
+        }
+
+        enum_data.StartFileReferenceNumber = next_ref;
     }
+
+    eprintln!("[{}] Initial catalog built: {} files enumerated", vol_name, count);
 }
 
-fn format_timestamp() -> String {
+/// Current wall-clock time as 100-ns ticks since the Unix epoch, used as a
+/// fallback for records that carry no timestamp.
+fn now_unix_ticks() -> i64 {
     use std::time::{SystemTime, UNIX_EPOCH};
-    
-    let duration = SystemTime::now()
+    SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .unwrap();
-    
-    let total_secs = duration.as_secs();
-    let millis = duration.subsec_millis();
-    
-    // Calculate hours, minutes, seconds
-    let hours = (total_secs / 3600) % 24;
-    let minutes = (total_secs / 60) % 60;
-    let seconds = total_secs % 60;
-    
-    format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, seconds, millis)
-}
\ No newline at end of file
+        .map(|d| (d.as_nanos() / 100) as i64)
+        .unwrap_or(0)
+}
+
+/// Convert a `USN_RECORD_V2.TimeStamp` into an ISO-8601 UTC timestamp.
+///
+/// The field is a FILETIME: 100-ns intervals since 1601-01-01. Shifting it to
+/// the Unix epoch (subtracting 11644473600 seconds) and splitting off the
+/// sub-second remainder yields a real, per-record date rather than the
+/// wall-clock-at-parse time every event used to share.
+fn format_timestamp(filetime: i64) -> String {
+    const TICKS_PER_SEC: i64 = 10_000_000;
+    const EPOCH_DIFF_SECS: i64 = 11_644_473_600; // 1601-01-01 -> 1970-01-01
+
+    // USN records may omit the timestamp (0); fall back to the current time
+    // rather than emitting a bogus 1601-01-01 date the frontend would index.
+    let unix_ticks = if filetime <= 0 {
+        now_unix_ticks()
+    } else {
+        filetime - EPOCH_DIFF_SECS * TICKS_PER_SEC
+    };
+    let secs = unix_ticks.div_euclid(TICKS_PER_SEC);
+    let millis = (unix_ticks.rem_euclid(TICKS_PER_SEC) / 10_000) as u32;
+
+    let days = secs.div_euclid(86_400);
+    let rem = secs.rem_euclid(86_400);
+    let (hour, minute, second) = (rem / 3600, (rem % 3600) / 60, rem % 60);
+
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, hour, minute, second, millis
+    )
+}
+
+/// Decompose a day count since 1970-01-01 into `(year, month, day)`.
+///
+/// Uses Howard Hinnant's branch-free civil-from-days algorithm so we avoid
+/// pulling in a date-time crate for this one conversion.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = if month <= 2 { y + 1 } else { y };
+    (year, month, day)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rules_parse_non_empty() {
+        assert!(!FilterRules::defaults().rules.is_empty());
+    }
+
+    #[test]
+    fn default_rules_exclude_tmp() {
+        let rules = FilterRules::defaults();
+        assert!(!rules.should_index(
+            "c:\\users\\me\\scratch.tmp",
+            "scratch.tmp",
+            ".tmp",
+            "Other"
+        ));
+    }
+}